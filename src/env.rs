@@ -0,0 +1,232 @@
+use anyhow::{anyhow, bail};
+use std::collections::HashMap;
+
+/// Abstraction over environment variable lookups so callers can be driven by
+/// a real process environment or by a fully controlled one in tests.
+pub trait Env {
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// The current machine's hostname. Separate from `get` because most
+    /// shells don't export a `HOSTNAME` variable to child processes, so this
+    /// has to come from the OS itself rather than the environment.
+    fn hostname(&self) -> Option<String>;
+}
+
+/// `Env` backed by the real process environment.
+pub struct SystemEnv;
+
+impl Env for SystemEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+
+    fn hostname(&self) -> Option<String> {
+        system_hostname()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn system_hostname() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|hostname| hostname.trim().to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_hostname() -> Option<String> {
+    let output = std::process::Command::new("hostname").output().ok()?;
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|hostname| hostname.trim().to_string())
+}
+
+/// `Env` backed by a fixed map, for tests that need deterministic, isolated
+/// environment values.
+#[derive(Default)]
+pub struct MockEnv {
+    vars: HashMap<String, String>,
+    hostname: Option<String>,
+}
+
+impl MockEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> &mut Self {
+        self.vars.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn set_hostname(&mut self, hostname: &str) -> &mut Self {
+        self.hostname = Some(hostname.to_string());
+        self
+    }
+}
+
+impl Env for MockEnv {
+    fn get(&self, key: &str) -> Option<String> {
+        self.vars.get(key).cloned()
+    }
+
+    fn hostname(&self) -> Option<String> {
+        self.hostname.clone()
+    }
+}
+
+/// Looks up the current machine's hostname through `env`.
+pub fn hostname(env: &dyn Env) -> Option<String> {
+    env.hostname()
+}
+
+/// Expands a leading `~`/`~/` to `HOME` and substitutes `$VAR` / `${VAR}`
+/// occurrences, using `env` for every lookup. Errors clearly when a
+/// referenced variable (including `HOME` for the tilde case) is unset.
+pub fn expand_path(value: &str, env: &dyn Env) -> Result<String, anyhow::Error> {
+    let with_home = expand_tilde(value, env)?;
+    expand_vars(&with_home, env)
+}
+
+fn expand_tilde(value: &str, env: &dyn Env) -> Result<String, anyhow::Error> {
+    if value == "~" || value.starts_with("~/") {
+        let home = env
+            .get("HOME")
+            .ok_or_else(|| anyhow!("cannot expand '~' in '{value}': HOME is not set"))?;
+        return Ok(format!("{home}{}", &value[1..]));
+    }
+    Ok(value.to_string())
+}
+
+fn expand_vars(value: &str, env: &dyn Env) -> Result<String, anyhow::Error> {
+    let mut result = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            let mut name = String::new();
+            let mut closed = false;
+            for c in chars.by_ref() {
+                if c == '}' {
+                    closed = true;
+                    break;
+                }
+                name.push(c);
+            }
+            if !closed {
+                bail!("unterminated '${{' in '{value}'");
+            }
+            result.push_str(&resolve_var(&name, value, env)?);
+            continue;
+        }
+
+        if matches!(chars.peek(), Some(c) if c.is_ascii_alphabetic() || *c == '_') {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            result.push_str(&resolve_var(&name, value, env)?);
+            continue;
+        }
+
+        result.push('$');
+    }
+    Ok(result)
+}
+
+fn resolve_var(name: &str, value: &str, env: &dyn Env) -> Result<String, anyhow::Error> {
+    env.get(name)
+        .ok_or_else(|| anyhow!("cannot expand '${name}' in '{value}': {name} is not set"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_with(vars: &[(&str, &str)]) -> MockEnv {
+        let mut env = MockEnv::new();
+        for (key, value) in vars {
+            env.set(key, value);
+        }
+        env
+    }
+
+    #[test]
+    fn hostname_reads_mock_hostname() {
+        let mut env = MockEnv::new();
+        env.set_hostname("karls-laptop");
+        assert_eq!(hostname(&env), Some("karls-laptop".to_string()));
+    }
+
+    #[test]
+    fn hostname_is_none_when_unset() {
+        let env = MockEnv::new();
+        assert_eq!(hostname(&env), None);
+    }
+
+    #[test]
+    fn expand_path_without_patterns_is_unchanged() {
+        let env = MockEnv::new();
+        assert_eq!(expand_path("nvim", &env).unwrap(), "nvim");
+    }
+
+    #[test]
+    fn expand_path_expands_leading_tilde() {
+        let env = env_with(&[("HOME", "/home/karl")]);
+        assert_eq!(
+            expand_path("~/.config/nvim", &env).unwrap(),
+            "/home/karl/.config/nvim"
+        );
+    }
+
+    #[test]
+    fn expand_path_bare_tilde_expands_to_home() {
+        let env = env_with(&[("HOME", "/home/karl")]);
+        assert_eq!(expand_path("~", &env).unwrap(), "/home/karl");
+    }
+
+    #[test]
+    fn expand_path_tilde_without_home_set_errors() {
+        let env = MockEnv::new();
+        assert!(expand_path("~/.config", &env).is_err());
+    }
+
+    #[test]
+    fn expand_path_substitutes_dollar_var() {
+        let env = env_with(&[("XDG_CONFIG_HOME", "/home/karl/.config")]);
+        assert_eq!(
+            expand_path("$XDG_CONFIG_HOME/nvim", &env).unwrap(),
+            "/home/karl/.config/nvim"
+        );
+    }
+
+    #[test]
+    fn expand_path_substitutes_braced_var() {
+        let env = env_with(&[("XDG_CONFIG_HOME", "/home/karl/.config")]);
+        assert_eq!(
+            expand_path("${XDG_CONFIG_HOME}/nvim", &env).unwrap(),
+            "/home/karl/.config/nvim"
+        );
+    }
+
+    #[test]
+    fn expand_path_unset_var_errors() {
+        let env = MockEnv::new();
+        assert!(expand_path("$XDG_CONFIG_HOME/nvim", &env).is_err());
+    }
+
+    #[test]
+    fn expand_path_unterminated_braced_var_errors() {
+        let env = MockEnv::new();
+        assert!(expand_path("${XDG_CONFIG_HOME/nvim", &env).is_err());
+    }
+}
@@ -1,7 +1,8 @@
-use crate::preflight::Manifest;
-use std::fs::{create_dir_all, remove_dir_all, remove_file};
-use std::os::unix::fs::symlink;
-use std::path::PathBuf;
+use crate::env::{Env, expand_path, hostname};
+use crate::preflight::{LinkStrategy, Manifest, ManifestItem};
+use anyhow::bail;
+use std::fs::{copy, create_dir_all, hard_link, read_dir, remove_dir_all, remove_file};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum AyarlaStatus {
@@ -9,47 +10,179 @@ pub enum AyarlaStatus {
     Warn,
 }
 
-pub fn lets_go(
-    base_path: PathBuf,
-    settings_dir_path: PathBuf,
-    manifest: Manifest,
-) -> Result<AyarlaStatus, anyhow::Error> {
-    let mut status = AyarlaStatus::Ok;
-    for item in manifest.manifest_items {
-        let source_path = settings_dir_path.join(item.source);
-        if !source_path.exists() {
-            status = AyarlaStatus::Warn;
+/// A single manifest item resolved to real filesystem paths, ready to be
+/// linked by [`apply`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlannedItem {
+    pub source_path: PathBuf,
+    pub destination_path: PathBuf,
+    pub strategy: LinkStrategy,
+}
+
+/// What [`apply`] will do for one manifest item, decided up front so a
+/// `--dry-run` can show it without touching the filesystem.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlannedAction {
+    CreateLink(PlannedItem),
+    ReplaceExisting(PlannedItem),
+    SkipExistsNoForce(PlannedItem),
+    MissingSource(PlannedItem),
+    /// The item uses `strategy = "hardlink"` but its source is a directory,
+    /// which no filesystem can hardlink. Caught during planning so `apply`
+    /// never deletes an existing destination only to fail afterwards.
+    InvalidHardlinkTarget(PlannedItem),
+}
+
+/// Resolves every applicable manifest item against the filesystem and
+/// decides what would happen to it, without performing any side effects.
+pub fn plan(
+    base_path: &Path,
+    settings_dir_path: &Path,
+    manifest: &Manifest,
+    env: &dyn Env,
+) -> Result<Vec<PlannedAction>, anyhow::Error> {
+    let mut actions = Vec::new();
+    for item in &manifest.manifest_items {
+        if !item_applies(item, env) {
+            continue;
+        }
+
+        let source = expand_path(&item.source, env)?;
+        let destination = expand_path(&item.destination, env)?;
+        let source_path = settings_dir_path.join(source);
+        let destination_path = base_path.join(destination);
+        let planned_item = PlannedItem {
+            source_path,
+            destination_path,
+            strategy: item.strategy,
+        };
+
+        if !planned_item.source_path.exists() {
+            actions.push(PlannedAction::MissingSource(planned_item));
             continue;
         }
 
-        let destination_path = base_path.join(item.destination);
-        if destination_path.exists() {
+        if planned_item.strategy == LinkStrategy::Hardlink && planned_item.source_path.is_dir() {
+            actions.push(PlannedAction::InvalidHardlinkTarget(planned_item));
+            continue;
+        }
+
+        if planned_item.destination_path.exists() {
             if item.force {
-                if destination_path.is_dir() {
-                    remove_dir_all(&destination_path)?;
+                actions.push(PlannedAction::ReplaceExisting(planned_item));
+            } else {
+                actions.push(PlannedAction::SkipExistsNoForce(planned_item));
+            }
+        } else {
+            actions.push(PlannedAction::CreateLink(planned_item));
+        }
+    }
+    Ok(actions)
+}
+
+/// Executes a plan produced by [`plan`], performing the filesystem side
+/// effects it describes.
+pub fn apply(actions: Vec<PlannedAction>) -> Result<AyarlaStatus, anyhow::Error> {
+    let mut status = AyarlaStatus::Ok;
+    for action in actions {
+        match action {
+            PlannedAction::MissingSource(_) => status = AyarlaStatus::Warn,
+            PlannedAction::InvalidHardlinkTarget(_) => status = AyarlaStatus::Warn,
+            PlannedAction::SkipExistsNoForce(_) => {}
+            PlannedAction::ReplaceExisting(item) => {
+                if item.destination_path.is_dir() {
+                    remove_dir_all(&item.destination_path)?;
                 } else {
-                    remove_file(&destination_path)?;
+                    remove_file(&item.destination_path)?;
                 }
-            } else {
-                continue;
+                create_link(&item)?;
             }
+            PlannedAction::CreateLink(item) => create_link(&item)?,
         }
-        let parent = destination_path.parent().unwrap();
-        if !parent.exists() {
-            create_dir_all(parent)?;
+    }
+    Ok(status)
+}
+
+fn create_link(item: &PlannedItem) -> Result<(), anyhow::Error> {
+    let parent = item.destination_path.parent().unwrap();
+    if !parent.exists() {
+        create_dir_all(parent)?;
+    }
+
+    let original = item.source_path.canonicalize()?;
+    let link = &item.destination_path;
+    match item.strategy {
+        LinkStrategy::Symlink => create_symlink(&original, link)?,
+        LinkStrategy::Hardlink => {
+            if original.is_dir() {
+                bail!("cannot hardlink a directory: {}", original.display());
+            }
+            hard_link(&original, link)?;
         }
+        LinkStrategy::Copy => copy_recursive(&original, link)?,
+    }
+    Ok(())
+}
 
-        let original = source_path.canonicalize()?;
-        let link = destination_path;
-        symlink(original, link)?;
+pub fn lets_go(
+    base_path: PathBuf,
+    settings_dir_path: PathBuf,
+    manifest: Manifest,
+    env: &dyn Env,
+) -> Result<AyarlaStatus, anyhow::Error> {
+    let actions = plan(&base_path, &settings_dir_path, &manifest, env)?;
+    apply(actions)
+}
+
+pub(crate) fn item_applies(item: &ManifestItem, env: &dyn Env) -> bool {
+    if let Some(os) = &item.os {
+        if !os.eq_ignore_ascii_case(std::env::consts::OS) {
+            return false;
+        }
     }
-    Ok(status)
+
+    if let Some(expected_hostname) = &item.hostname {
+        if hostname(env).as_deref() != Some(expected_hostname.as_str()) {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(unix)]
+fn create_symlink(original: &Path, link: &Path) -> Result<(), anyhow::Error> {
+    std::os::unix::fs::symlink(original, link)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn create_symlink(original: &Path, link: &Path) -> Result<(), anyhow::Error> {
+    if original.is_dir() {
+        std::os::windows::fs::symlink_dir(original, link)?;
+    } else {
+        std::os::windows::fs::symlink_file(original, link)?;
+    }
+    Ok(())
+}
+
+fn copy_recursive(source: &Path, destination: &Path) -> Result<(), anyhow::Error> {
+    if source.is_dir() {
+        create_dir_all(destination)?;
+        for entry in read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+    } else {
+        copy(source, destination)?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::preflight::ManifestItem;
+    use crate::env::MockEnv;
     use std::fs::{self, DirEntry, File, create_dir_all};
     use tempfile::tempdir;
 
@@ -60,11 +193,17 @@ mod tests {
                     source: ".tmux.conf".to_string(),
                     destination: ".tmux.conf".to_string(),
                     force: false,
+                    strategy: LinkStrategy::Symlink,
+                    os: None,
+                    hostname: None,
                 },
                 ManifestItem {
                     source: "nvim".to_string(),
                     destination: ".config/nvim".to_string(),
                     force: false,
+                    strategy: LinkStrategy::Symlink,
+                    os: None,
+                    hostname: None,
                 },
             ],
         }
@@ -82,6 +221,7 @@ mod tests {
             home_dir_path.to_path_buf(),
             settings_dir_path.to_path_buf(),
             get_test_manifest(),
+            &MockEnv::new(),
         );
 
         assert_eq!(result.unwrap(), AyarlaStatus::Warn);
@@ -107,6 +247,7 @@ mod tests {
             home_dir_path.to_path_buf(),
             settings_dir_path.to_path_buf(),
             get_test_manifest(),
+            &MockEnv::new(),
         );
 
         assert_eq!(result.unwrap(), AyarlaStatus::Warn);
@@ -118,6 +259,51 @@ mod tests {
         assert!(just_as_expected);
     }
 
+    #[test]
+    fn lets_go_expands_env_vars_and_tilde_in_manifest_paths() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let mut env = MockEnv::new();
+        env.set(
+            "HOME",
+            home_dir_path.to_str().expect("to get str from path"),
+        );
+        env.set("MY_SETTING", "nvim");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: "$MY_SETTING".to_string(),
+                destination: "~/.config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &env,
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        assert!(
+            home_dir_path
+                .join(".config/nvim")
+                .symlink_metadata()
+                .expect("to stat symlink")
+                .file_type()
+                .is_symlink()
+        );
+    }
+
     #[test]
     fn lets_go_everything_configured_and_ok() {
         let temp_dir = tempdir().expect("to create temp_dir");
@@ -135,6 +321,7 @@ mod tests {
             home_dir_path.to_path_buf(),
             settings_dir_path.to_path_buf(),
             get_test_manifest(),
+            &MockEnv::new(),
         );
 
         let dir_items = fs::read_dir(home_dir_path)
@@ -159,4 +346,454 @@ mod tests {
             .all(|d| d.file_name() == "nvim" && d.file_type().unwrap().is_symlink());
         assert!(just_as_expected);
     }
+
+    #[test]
+    fn lets_go_hardlink_strategy_links_file() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Hardlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &MockEnv::new(),
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        let linked = home_dir_path.join(".tmux.conf");
+        assert!(!linked.symlink_metadata().expect("to stat file").is_symlink());
+        assert!(linked.exists());
+    }
+
+    #[test]
+    fn lets_go_hardlink_strategy_warns_on_directory_without_touching_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Hardlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &MockEnv::new(),
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Warn);
+        assert!(!home_dir_path.join(".config/nvim").exists());
+    }
+
+    #[test]
+    fn lets_go_hardlink_strategy_does_not_delete_existing_destination_when_forced() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        let home_dir_path = temp_dir.path().join("home");
+        let existing_destination = home_dir_path.join(".config/nvim");
+        create_dir_all(&existing_destination).expect("to create existing destination");
+        let dont_lose_this = existing_destination.join("dont_lose_this");
+        File::create(&dont_lose_this).expect("to create file worth keeping");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: true,
+                strategy: LinkStrategy::Hardlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &MockEnv::new(),
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Warn);
+        assert!(dont_lose_this.exists());
+    }
+
+    #[test]
+    fn lets_go_copy_strategy_copies_directory_contents() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        let nvim_conf_path = nvim_dir_path.join(".nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        File::create(&nvim_conf_path).expect("to create .nvim file");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Copy,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &MockEnv::new(),
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        let copied_dir = home_dir_path.join(".config/nvim");
+        assert!(!copied_dir.symlink_metadata().expect("to stat dir").is_symlink());
+        assert!(copied_dir.join(".nvim").exists());
+    }
+
+    #[test]
+    fn lets_go_skips_item_with_mismatched_os_without_warning() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: Some("not-a-real-os".to_string()),
+                hostname: None,
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &MockEnv::new(),
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        assert_eq!(
+            fs::read_dir(home_dir_path)
+                .expect("to read home dir")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn lets_go_skips_item_with_mismatched_hostname() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let mut env = MockEnv::new();
+        env.set_hostname("other-machine");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: Some("karls-laptop".to_string()),
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &env,
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        assert_eq!(
+            fs::read_dir(home_dir_path)
+                .expect("to read home dir")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn lets_go_applies_item_with_matching_hostname() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let mut env = MockEnv::new();
+        env.set_hostname("karls-laptop");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: Some("karls-laptop".to_string()),
+            }],
+        };
+
+        let result = lets_go(
+            home_dir_path.to_path_buf(),
+            settings_dir_path.to_path_buf(),
+            manifest,
+            &env,
+        );
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        assert!(
+            home_dir_path
+                .join(".tmux.conf")
+                .symlink_metadata()
+                .expect("to stat symlink")
+                .is_symlink()
+        );
+    }
+
+    #[test]
+    fn plan_reports_missing_source() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let actions = plan(
+            &home_dir_path,
+            &settings_dir_path,
+            &get_test_manifest(),
+            &MockEnv::new(),
+        )
+        .expect("to get plan");
+
+        assert_eq!(actions.len(), 2);
+        assert!(
+            actions
+                .iter()
+                .all(|a| matches!(a, PlannedAction::MissingSource(_)))
+        );
+    }
+
+    #[test]
+    fn plan_reports_create_link_for_new_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        assert!(matches!(actions[0], PlannedAction::CreateLink(_)));
+    }
+
+    #[test]
+    fn plan_reports_skip_exists_no_force() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        File::create(home_dir_path.join(".tmux.conf")).expect("to create existing destination");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        assert!(matches!(actions[0], PlannedAction::SkipExistsNoForce(_)));
+    }
+
+    #[test]
+    fn plan_reports_replace_existing_when_forced() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        File::create(home_dir_path.join(".tmux.conf")).expect("to create existing destination");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: true,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        assert!(matches!(actions[0], PlannedAction::ReplaceExisting(_)));
+    }
+
+    #[test]
+    fn plan_reports_invalid_hardlink_target_for_directory_source() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: true,
+                strategy: LinkStrategy::Hardlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        assert!(matches!(
+            actions[0],
+            PlannedAction::InvalidHardlinkTarget(_)
+        ));
+    }
+
+    #[test]
+    fn plan_does_not_produce_action_for_skipped_os_filter() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: Some("not-a-real-os".to_string()),
+                hostname: None,
+            }],
+        };
+
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn apply_executes_create_link_actions() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![ManifestItem {
+                source: ".tmux.conf".to_string(),
+                destination: ".tmux.conf".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            }],
+        };
+        let actions = plan(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get plan");
+
+        let result = apply(actions);
+
+        assert_eq!(result.unwrap(), AyarlaStatus::Ok);
+        assert!(
+            home_dir_path
+                .join(".tmux.conf")
+                .symlink_metadata()
+                .expect("to stat symlink")
+                .is_symlink()
+        );
+    }
 }
@@ -1,9 +1,11 @@
 use clap::{Parser, Subcommand};
-use std::env::var;
+use env::{Env, SystemEnv};
 use std::path::PathBuf;
 
+mod env;
 mod heyho;
 mod preflight;
+mod status;
 
 #[derive(Parser)]
 #[command(
@@ -22,43 +24,320 @@ struct Cli {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Print the bootstrap plan without touching the filesystem (scaffold and
+    /// add ignore this flag and always write)
+    #[arg(long, global = true)]
+    dry_run: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    // Scaffold {
-    //     #[arg(short, long)]
-    //     todo: String,
-    // },
     /// Bootstraps everything in your manifest within your settings directory
     #[command(arg_required_else_help = true, alias = "lan")]
     Bootstrap {
         #[arg(short, long)]
         settings_directory: String,
     },
-    // #[command(arg_required_else_help = true)]
-    // Add {
-    //     #[arg(short, long)]
-    //     todo: String,
-    // },
+    /// Reports the current on-disk state of every manifest item
+    #[command(arg_required_else_help = true)]
+    Status {
+        #[arg(short, long)]
+        settings_directory: String,
+    },
+    /// Creates a settings directory pre-populated with an empty manifest.toml
+    #[command(arg_required_else_help = true)]
+    Scaffold {
+        #[arg(short, long)]
+        settings_directory: String,
+    },
+    /// Appends a new entry to the manifest.toml in your settings directory
+    #[command(arg_required_else_help = true)]
+    Add {
+        #[arg(short, long)]
+        settings_directory: String,
+        #[arg(long)]
+        source: String,
+        #[arg(long)]
+        destination: String,
+        #[arg(short, long)]
+        force: bool,
+        #[arg(long, value_enum, default_value = "symlink")]
+        strategy: StrategyArg,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum StrategyArg {
+    Symlink,
+    Hardlink,
+    Copy,
+}
+
+impl From<StrategyArg> for preflight::LinkStrategy {
+    fn from(value: StrategyArg) -> Self {
+        match value {
+            StrategyArg::Symlink => preflight::LinkStrategy::Symlink,
+            StrategyArg::Hardlink => preflight::LinkStrategy::Hardlink,
+            StrategyArg::Copy => preflight::LinkStrategy::Copy,
+        }
+    }
 }
 
-fn get_home_from_env() -> Result<PathBuf, anyhow::Error> {
-    let home = var("HOME")?;
+fn get_home_from_env(env: &dyn Env) -> Result<PathBuf, anyhow::Error> {
+    let home = env
+        .get("HOME")
+        .ok_or_else(|| anyhow::anyhow!("HOME is not set"))?;
     Ok(PathBuf::from(home))
 }
 
 fn main() -> Result<(), anyhow::Error> {
-    let home = get_home_from_env()?;
-    let cli = Cli::parse();
+    let env = SystemEnv;
+    let args = expand_alias(std::env::args().collect());
+    let cli = Cli::parse_from(args);
     match cli.command {
         Commands::Bootstrap { settings_directory } => {
+            let home = get_home_from_env(&env)?;
             let (settings_dir_path, manifest) = preflight::checks(settings_directory.as_str())?;
-            heyho::lets_go(home.to_path_buf(), settings_dir_path, manifest)?;
+            let actions = heyho::plan(&home, &settings_dir_path, &manifest, &env)?;
+            if cli.dry_run {
+                print_plan(&actions);
+            } else {
+                heyho::apply(actions)?;
+            }
+        }
+        Commands::Status { settings_directory } => {
+            let home = get_home_from_env(&env)?;
+            let (settings_dir_path, manifest) = preflight::checks(settings_directory.as_str())?;
+            let report = status::status(&home, &settings_dir_path, &manifest, &env)?;
+            print_status(&report);
+        }
+        Commands::Scaffold { settings_directory } => {
+            preflight::scaffold(settings_directory.as_str())?;
+        }
+        Commands::Add {
+            settings_directory,
+            source,
+            destination,
+            force,
+            strategy,
+        } => {
+            preflight::add_item(
+                settings_directory.as_str(),
+                preflight::ManifestItem {
+                    source,
+                    destination,
+                    force,
+                    strategy: strategy.into(),
+                    os: None,
+                    hostname: None,
+                },
+            )?;
         }
     }
 
     Ok(())
 }
+
+/// Rewrites the subcommand word before `clap` parses the arguments, if it
+/// matches a user-defined alias from the `[aliases]` table of the
+/// manifest.toml in whatever settings directory was named via
+/// `-s`/`--settings-directory`.
+fn expand_alias(mut args: Vec<String>) -> Vec<String> {
+    let Some(settings_directory) = find_settings_directory(&args) else {
+        return args;
+    };
+    let aliases = preflight::read_aliases(&settings_directory);
+    if aliases.aliases.is_empty() {
+        return args;
+    }
+
+    let subcommand_index = args.iter().skip(1).position(|a| !a.starts_with('-'));
+    if let Some(index) = subcommand_index {
+        let index = index + 1;
+        if let Some(expansion) = aliases.aliases.get(&args[index]) {
+            args[index] = expansion.clone();
+        }
+    }
+    args
+}
+
+fn find_settings_directory(args: &[String]) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if let Some(value) = arg.strip_prefix("--settings-directory=") {
+            return Some(value.to_string());
+        }
+        if let Some(value) = arg.strip_prefix("-s=") {
+            return Some(value.to_string());
+        }
+        if arg == "--settings-directory" || arg == "-s" {
+            return args.get(i + 1).cloned();
+        }
+        None
+    })
+}
+
+fn print_plan(actions: &[heyho::PlannedAction]) {
+    for action in actions {
+        match action {
+            heyho::PlannedAction::CreateLink(item) => {
+                println!("create:  {}", item.destination_path.display())
+            }
+            heyho::PlannedAction::ReplaceExisting(item) => {
+                println!("replace: {}", item.destination_path.display())
+            }
+            heyho::PlannedAction::SkipExistsNoForce(item) => {
+                println!(
+                    "skip:    {} (already exists, not forced)",
+                    item.destination_path.display()
+                )
+            }
+            heyho::PlannedAction::MissingSource(item) => {
+                println!(
+                    "warn:    {} (source is missing)",
+                    item.destination_path.display()
+                )
+            }
+            heyho::PlannedAction::InvalidHardlinkTarget(item) => {
+                println!(
+                    "warn:    {} (cannot hardlink a directory)",
+                    item.destination_path.display()
+                )
+            }
+        }
+    }
+}
+
+fn print_status(report: &[status::ItemStatus]) {
+    for item in report {
+        let state = match item.state {
+            status::ItemState::Linked => "linked",
+            status::ItemState::Missing => "missing",
+            status::ItemState::Conflicting => "conflicting",
+            status::ItemState::Stale => "stale",
+        };
+        println!("{}: {}", item.destination_path.display(), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::write;
+    use tempfile::tempdir;
+
+    fn settings_dir_with_manifest(manifest_content: &str) -> tempfile::TempDir {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        write(temp_dir.path().join("manifest.toml"), manifest_content)
+            .expect("to write manifest.toml");
+        temp_dir
+    }
+
+    #[test]
+    fn expand_alias_passes_through_without_settings_directory() {
+        let args = vec!["ayarla".to_string(), "bootstrap".to_string()];
+        assert_eq!(expand_alias(args.clone()), args);
+    }
+
+    #[test]
+    fn expand_alias_passes_through_when_no_aliases_configured() {
+        let temp_dir = settings_dir_with_manifest("");
+        let settings_directory = temp_dir.path().to_str().expect("to get str from path");
+        let args = vec![
+            "ayarla".to_string(),
+            "bootstrap".to_string(),
+            "-s".to_string(),
+            settings_directory.to_string(),
+        ];
+
+        assert_eq!(expand_alias(args.clone()), args);
+    }
+
+    #[test]
+    fn expand_alias_expands_configured_alias() {
+        let temp_dir = settings_dir_with_manifest("[aliases]\nup = \"bootstrap\"\n");
+        let settings_directory = temp_dir.path().to_str().expect("to get str from path");
+        let args = vec![
+            "ayarla".to_string(),
+            "up".to_string(),
+            "-s".to_string(),
+            settings_directory.to_string(),
+        ];
+
+        let expanded = expand_alias(args);
+
+        assert_eq!(expanded[1], "bootstrap");
+    }
+
+    #[test]
+    fn expand_alias_skips_global_flags_to_find_the_subcommand() {
+        let temp_dir = settings_dir_with_manifest("[aliases]\nup = \"bootstrap\"\n");
+        let settings_directory = temp_dir.path().to_str().expect("to get str from path");
+        let args = vec![
+            "ayarla".to_string(),
+            "--dry-run".to_string(),
+            "up".to_string(),
+            "-s".to_string(),
+            settings_directory.to_string(),
+        ];
+
+        let expanded = expand_alias(args);
+
+        assert_eq!(expanded[2], "bootstrap");
+    }
+
+    #[test]
+    fn find_settings_directory_reads_short_flag_with_space() {
+        let args = vec![
+            "ayarla".to_string(),
+            "-s".to_string(),
+            "/tmp/settings".to_string(),
+        ];
+        assert_eq!(
+            find_settings_directory(&args),
+            Some("/tmp/settings".to_string())
+        );
+    }
+
+    #[test]
+    fn find_settings_directory_reads_short_flag_with_equals() {
+        let args = vec!["ayarla".to_string(), "-s=/tmp/settings".to_string()];
+        assert_eq!(
+            find_settings_directory(&args),
+            Some("/tmp/settings".to_string())
+        );
+    }
+
+    #[test]
+    fn find_settings_directory_reads_long_flag_with_space() {
+        let args = vec![
+            "ayarla".to_string(),
+            "--settings-directory".to_string(),
+            "/tmp/settings".to_string(),
+        ];
+        assert_eq!(
+            find_settings_directory(&args),
+            Some("/tmp/settings".to_string())
+        );
+    }
+
+    #[test]
+    fn find_settings_directory_reads_long_flag_with_equals() {
+        let args = vec![
+            "ayarla".to_string(),
+            "--settings-directory=/tmp/settings".to_string(),
+        ];
+        assert_eq!(
+            find_settings_directory(&args),
+            Some("/tmp/settings".to_string())
+        );
+    }
+
+    #[test]
+    fn find_settings_directory_is_none_without_flag() {
+        let args = vec!["ayarla".to_string(), "bootstrap".to_string()];
+        assert_eq!(find_settings_directory(&args), None);
+    }
+}
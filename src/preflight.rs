@@ -1,31 +1,126 @@
 use anyhow::bail;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs::{DirEntry, read_dir, read_to_string},
+    collections::HashMap,
+    fs::{DirEntry, create_dir_all, read_dir, read_to_string, write},
     path::{Path, PathBuf},
 };
 
 const MANIFEST_FILE_NAME: &str = "manifest.toml";
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStrategy {
+    #[default]
+    Symlink,
+    Hardlink,
+    Copy,
+}
+
+#[derive(Deserialize, Serialize)]
 pub struct ManifestItem {
     pub source: String,
     pub destination: String,
     #[serde(default)]
     pub force: bool,
+    #[serde(default)]
+    pub strategy: LinkStrategy,
+    /// Only apply this item on the given OS, e.g. "macos", "linux", "windows".
+    #[serde(default)]
+    pub os: Option<String>,
+    /// Only apply this item on the given hostname.
+    #[serde(default)]
+    pub hostname: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Manifest {
     pub manifest_items: Vec<ManifestItem>,
 }
 
+/// User-defined command aliases, read from the `[aliases]` table alongside
+/// `manifest_items` in `manifest.toml`.
+#[derive(Deserialize, Default)]
+pub struct Aliases {
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+}
+
 pub fn checks(settings_directory: &str) -> Result<(PathBuf, Manifest), anyhow::Error> {
     let are_we_ready = are_we_ready_for_takeoff(settings_directory)?;
     let manifest = red_manifesto(are_we_ready.manifest_content)?;
     Ok((are_we_ready.settings_dir_path, manifest))
 }
 
+/// Creates `settings_directory` if needed and writes it an empty `manifest.toml`.
+pub fn scaffold(settings_directory: &str) -> Result<(), anyhow::Error> {
+    let settings_directory_path = Path::new(settings_directory);
+    create_dir_all(settings_directory_path)?;
+
+    let manifest_path = settings_directory_path.join(MANIFEST_FILE_NAME);
+    if manifest_path.exists() {
+        bail!("manifest.toml already exists in {}", settings_directory);
+    }
+
+    write_toml(
+        &manifest_path,
+        &Manifest {
+            manifest_items: Vec::new(),
+        },
+    )
+}
+
+/// Appends `item` to the `manifest.toml` in `settings_directory`, preserving
+/// any other top-level tables already in the file (e.g. `[aliases]`) by
+/// round-tripping through the raw TOML table and only touching the
+/// `manifest_items` key, instead of re-serializing a `Manifest` that only
+/// knows about that one key.
+pub fn add_item(settings_directory: &str, item: ManifestItem) -> Result<(), anyhow::Error> {
+    let manifest_path = Path::new(settings_directory).join(MANIFEST_FILE_NAME);
+    if !manifest_path.exists() {
+        bail!(
+            "Directory does not contain manifest.toml: {}",
+            settings_directory
+        );
+    }
+
+    let manifest_content = read_to_string(&manifest_path)?;
+    let mut table: toml::Table = match toml::from_str(manifest_content.as_str()) {
+        Ok(table) => table,
+        Err(e) => bail!("Failed to parse manifest: {}", e),
+    };
+
+    let mut manifest_items: Vec<ManifestItem> = match table.get("manifest_items") {
+        Some(value) => value.clone().try_into()?,
+        None => Vec::new(),
+    };
+    manifest_items.push(item);
+    table.insert(
+        "manifest_items".to_string(),
+        toml::Value::try_from(&manifest_items)?,
+    );
+
+    write_toml(&manifest_path, &table)
+}
+
+fn write_toml<T: Serialize>(manifest_path: &Path, value: &T) -> Result<(), anyhow::Error> {
+    let serialized = toml::to_string_pretty(value)?;
+    write(manifest_path, serialized)?;
+    Ok(())
+}
+
+/// Reads the `[aliases]` table from `manifest.toml` in `settings_directory`,
+/// if both the directory and the file exist. Any other read or parse
+/// failure is treated as "no aliases configured" rather than an error, since
+/// alias expansion runs before the real argument parsing and validation.
+pub fn read_aliases(settings_directory: &str) -> Aliases {
+    let manifest_path = Path::new(settings_directory).join(MANIFEST_FILE_NAME);
+    read_to_string(manifest_path)
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
 struct WeAreReadyMaybe {
     settings_dir_path: PathBuf,
     manifest_content: String,
@@ -283,6 +378,48 @@ destination = "./config/nvim"
         assert_eq!(manifest.manifest_items[0].source, "nvim");
         assert_eq!(manifest.manifest_items[0].destination, "./config/nvim");
         assert_eq!(manifest.manifest_items[0].force, false);
+        assert_eq!(manifest.manifest_items[0].strategy, LinkStrategy::Symlink);
+    }
+
+    #[test]
+    fn red_manifesto_with_explicit_strategy_assert_ok() {
+        let content = r#"
+[[manifest_items]]
+source = "nvim"
+destination = "./config/nvim"
+strategy = "copy"
+"#;
+
+        let manifest = red_manifesto(String::from(content)).expect("to get result");
+
+        assert_eq!(manifest.manifest_items.len(), 1);
+        assert_eq!(manifest.manifest_items[0].strategy, LinkStrategy::Copy);
+    }
+
+    #[test]
+    fn red_manifesto_with_os_and_hostname_filters_assert_ok() {
+        let content = r#"
+[[manifest_items]]
+source = "nvim"
+destination = "./config/nvim"
+os = "macos"
+
+[[manifest_items]]
+source = "tmux.conf"
+destination = ".tmux.conf"
+hostname = "karls-laptop"
+"#;
+
+        let manifest = red_manifesto(String::from(content)).expect("to get result");
+
+        assert_eq!(manifest.manifest_items.len(), 2);
+        assert_eq!(manifest.manifest_items[0].os, Some("macos".to_string()));
+        assert_eq!(manifest.manifest_items[0].hostname, None);
+        assert_eq!(manifest.manifest_items[1].os, None);
+        assert_eq!(
+            manifest.manifest_items[1].hostname,
+            Some("karls-laptop".to_string())
+        );
     }
 
     #[test]
@@ -353,4 +490,164 @@ force = true
         assert_eq!(result.1.manifest_items[0].destination, ".tmux.conf");
         assert_eq!(result.1.manifest_items[0].force, true);
     }
+
+    #[test]
+    fn scaffold_creates_directory_and_empty_manifest() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("new_settings_dir");
+
+        scaffold(dir_path.to_str().expect("to get str from path")).expect("to scaffold");
+
+        let manifest_content =
+            read_to_string(dir_path.join(MANIFEST_FILE_NAME)).expect("to read manifest.toml");
+        let manifest = red_manifesto(manifest_content).expect("to parse manifest");
+        assert!(manifest.manifest_items.is_empty());
+    }
+
+    #[test]
+    fn scaffold_fails_if_manifest_already_exists() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("existing_settings_dir");
+        create_dir_all(&dir_path).expect("to create dir");
+        File::create(dir_path.join(MANIFEST_FILE_NAME)).expect("to create manifest.toml");
+
+        let result = scaffold(dir_path.to_str().expect("to get str from path"));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn add_item_appends_to_existing_manifest() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&dir_path).expect("to create dir");
+        let manifest = dir_path.join(MANIFEST_FILE_NAME);
+        let mut manifest_file = File::create(&manifest).expect("to create manifest.toml");
+        manifest_file
+            .write(
+                r#"
+[[manifest_items]]
+source = "tmux.conf"
+destination = ".tmux.conf"
+"#
+                .as_bytes(),
+            )
+            .expect("to write to manifest");
+
+        add_item(
+            dir_path.to_str().expect("to get str from path"),
+            ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            },
+        )
+        .expect("to add item");
+
+        let manifest_content = read_to_string(&manifest).expect("to read manifest.toml");
+        let manifest = red_manifesto(manifest_content).expect("to parse manifest");
+        assert_eq!(manifest.manifest_items.len(), 2);
+        assert_eq!(manifest.manifest_items[0].source, "tmux.conf");
+        assert_eq!(manifest.manifest_items[1].source, "nvim");
+        assert_eq!(manifest.manifest_items[1].destination, ".config/nvim");
+    }
+
+    #[test]
+    fn add_item_preserves_existing_aliases_table() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&dir_path).expect("to create dir");
+        let manifest = dir_path.join(MANIFEST_FILE_NAME);
+        let mut manifest_file = File::create(&manifest).expect("to create manifest.toml");
+        manifest_file
+            .write(
+                r#"
+[[manifest_items]]
+source = "tmux.conf"
+destination = ".tmux.conf"
+
+[aliases]
+up = "bootstrap"
+"#
+                .as_bytes(),
+            )
+            .expect("to write to manifest");
+
+        add_item(
+            dir_path.to_str().expect("to get str from path"),
+            ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            },
+        )
+        .expect("to add item");
+
+        let manifest_content = read_to_string(&manifest).expect("to read manifest.toml");
+        let manifest = red_manifesto(manifest_content).expect("to parse manifest");
+        assert_eq!(manifest.manifest_items.len(), 2);
+        let aliases = read_aliases(dir_path.to_str().expect("to get str from path"));
+        assert_eq!(aliases.aliases.get("up"), Some(&"bootstrap".to_string()));
+    }
+
+    #[test]
+    fn add_item_fails_without_manifest() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&dir_path).expect("to create dir");
+
+        let result = add_item(
+            dir_path.to_str().expect("to get str from path"),
+            ManifestItem {
+                source: "nvim".to_string(),
+                destination: ".config/nvim".to_string(),
+                force: false,
+                strategy: LinkStrategy::Symlink,
+                os: None,
+                hostname: None,
+            },
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn read_aliases_returns_configured_table() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&dir_path).expect("to create dir");
+        let manifest = dir_path.join(MANIFEST_FILE_NAME);
+        let mut manifest_file = File::create(&manifest).expect("to create manifest.toml");
+        manifest_file
+            .write(
+                r#"
+manifest_items = []
+
+[aliases]
+up = "bootstrap"
+"#
+                .as_bytes(),
+            )
+            .expect("to write to manifest");
+
+        let aliases = read_aliases(dir_path.to_str().expect("to get str from path"));
+
+        assert_eq!(aliases.aliases.get("up"), Some(&"bootstrap".to_string()));
+    }
+
+    #[test]
+    fn read_aliases_is_empty_without_manifest() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let dir_path = temp_dir.path().join("no_settings_dir");
+
+        let aliases = read_aliases(dir_path.to_str().expect("to get str from path"));
+
+        assert!(aliases.aliases.is_empty());
+    }
 }
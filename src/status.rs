@@ -0,0 +1,359 @@
+use crate::env::{Env, expand_path};
+use crate::preflight::{LinkStrategy, Manifest};
+use std::fs::{read, read_dir};
+use std::path::{Path, PathBuf};
+
+/// How a manifest item's destination currently looks on disk, independent of
+/// any pending plan.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ItemState {
+    /// The destination matches what this item's strategy would produce.
+    Linked,
+    /// The destination does not exist.
+    Missing,
+    /// The destination exists but wasn't produced by this item's strategy
+    /// (e.g. a plain file where a symlink was expected).
+    Conflicting,
+    /// The destination was produced by this item's strategy, but no longer
+    /// matches the source (a symlink pointing elsewhere, a hardlink replaced
+    /// by a different file, or copied contents that have diverged).
+    Stale,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ItemStatus {
+    pub destination_path: PathBuf,
+    pub state: ItemState,
+}
+
+/// Reports the current on-disk state of every applicable manifest item,
+/// without making any changes.
+pub fn status(
+    base_path: &Path,
+    settings_dir_path: &Path,
+    manifest: &Manifest,
+    env: &dyn Env,
+) -> Result<Vec<ItemStatus>, anyhow::Error> {
+    let mut report = Vec::new();
+    for item in &manifest.manifest_items {
+        if !crate::heyho::item_applies(item, env) {
+            continue;
+        }
+
+        let source = expand_path(&item.source, env)?;
+        let destination = expand_path(&item.destination, env)?;
+        let source_path = settings_dir_path.join(source);
+        let destination_path = base_path.join(destination);
+
+        let state = item_state(&source_path, &destination_path, item.strategy);
+        report.push(ItemStatus {
+            destination_path,
+            state,
+        });
+    }
+    Ok(report)
+}
+
+fn item_state(source_path: &Path, destination_path: &Path, strategy: LinkStrategy) -> ItemState {
+    if destination_path.symlink_metadata().is_err() {
+        return ItemState::Missing;
+    }
+
+    match strategy {
+        LinkStrategy::Symlink => symlink_item_state(source_path, destination_path),
+        LinkStrategy::Hardlink => hardlink_item_state(source_path, destination_path),
+        LinkStrategy::Copy => copy_item_state(source_path, destination_path),
+    }
+}
+
+fn symlink_item_state(source_path: &Path, destination_path: &Path) -> ItemState {
+    if !destination_path.is_symlink() {
+        return ItemState::Conflicting;
+    }
+
+    let resolved_destination = destination_path.canonicalize();
+    let resolved_source = source_path.canonicalize();
+    match (resolved_destination, resolved_source) {
+        (Ok(destination), Ok(source)) if destination == source => ItemState::Linked,
+        _ => ItemState::Stale,
+    }
+}
+
+#[cfg(unix)]
+fn hardlink_item_state(source_path: &Path, destination_path: &Path) -> ItemState {
+    use std::os::unix::fs::MetadataExt;
+
+    if destination_path.is_symlink() {
+        return ItemState::Conflicting;
+    }
+
+    let (Ok(source_meta), Ok(destination_meta)) =
+        (source_path.metadata(), destination_path.metadata())
+    else {
+        return ItemState::Conflicting;
+    };
+
+    if source_meta.dev() == destination_meta.dev() && source_meta.ino() == destination_meta.ino() {
+        ItemState::Linked
+    } else {
+        ItemState::Stale
+    }
+}
+
+#[cfg(windows)]
+fn hardlink_item_state(source_path: &Path, destination_path: &Path) -> ItemState {
+    // Windows doesn't expose a simple way to compare hardlink identity
+    // through `std::fs`, so fall back to the same content comparison used
+    // for the copy strategy.
+    copy_item_state(source_path, destination_path)
+}
+
+fn copy_item_state(source_path: &Path, destination_path: &Path) -> ItemState {
+    if destination_path.is_symlink() {
+        return ItemState::Conflicting;
+    }
+
+    match copy_contents_match(source_path, destination_path) {
+        Some(true) => ItemState::Linked,
+        Some(false) => ItemState::Stale,
+        None => ItemState::Conflicting,
+    }
+}
+
+/// Compares `source_path` and `destination_path` byte-for-byte (recursively,
+/// for directories). `None` means they're not even comparable (e.g. one is a
+/// file and the other a directory).
+fn copy_contents_match(source_path: &Path, destination_path: &Path) -> Option<bool> {
+    if source_path.is_dir() != destination_path.is_dir() {
+        return None;
+    }
+
+    if !source_path.is_dir() {
+        return Some(read(source_path).ok()? == read(destination_path).ok()?);
+    }
+
+    let mut matches = true;
+    for entry in read_dir(source_path).ok()? {
+        let entry = entry.ok()?;
+        let destination_entry = destination_path.join(entry.file_name());
+        match copy_contents_match(&entry.path(), &destination_entry)? {
+            true => {}
+            false => matches = false,
+        }
+    }
+    Some(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::MockEnv;
+    use crate::preflight::ManifestItem;
+    use std::fs::{File, create_dir_all, hard_link, write};
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    fn manifest_item_for(source: &str, destination: &str) -> ManifestItem {
+        manifest_item_with_strategy(source, destination, LinkStrategy::Symlink)
+    }
+
+    fn manifest_item_with_strategy(
+        source: &str,
+        destination: &str,
+        strategy: LinkStrategy,
+    ) -> ManifestItem {
+        ManifestItem {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            force: false,
+            strategy,
+            os: None,
+            hostname: None,
+        }
+    }
+
+    #[test]
+    fn status_reports_missing_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_for(".tmux.conf", ".tmux.conf")],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Missing);
+    }
+
+    #[test]
+    fn status_reports_linked_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        symlink(&tmux_conf, home_dir_path.join(".tmux.conf")).expect("to create symlink");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_for(".tmux.conf", ".tmux.conf")],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Linked);
+    }
+
+    #[test]
+    fn status_reports_conflicting_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        File::create(home_dir_path.join(".tmux.conf")).expect("to create a real file");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_for(".tmux.conf", ".tmux.conf")],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Conflicting);
+    }
+
+    #[test]
+    fn status_reports_stale_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        let elsewhere = temp_dir.path().join("elsewhere");
+        File::create(&elsewhere).expect("to create unrelated file");
+        symlink(&elsewhere, home_dir_path.join(".tmux.conf")).expect("to create symlink");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_for(".tmux.conf", ".tmux.conf")],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Stale);
+    }
+
+    #[test]
+    fn status_reports_linked_hardlink_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        hard_link(&tmux_conf, home_dir_path.join(".tmux.conf")).expect("to create hardlink");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_with_strategy(
+                ".tmux.conf",
+                ".tmux.conf",
+                LinkStrategy::Hardlink,
+            )],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Linked);
+    }
+
+    #[test]
+    fn status_reports_stale_hardlink_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let tmux_conf = settings_dir_path.join(".tmux.conf");
+        create_dir_all(&settings_dir_path).expect("to create settings_dir");
+        File::create(&tmux_conf).expect("to create .tmux.conf");
+        let home_dir_path = temp_dir.path().join("home");
+        create_dir_all(&home_dir_path).expect("to create home dir");
+        File::create(home_dir_path.join(".tmux.conf")).expect("to create an unrelated file");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_with_strategy(
+                ".tmux.conf",
+                ".tmux.conf",
+                LinkStrategy::Hardlink,
+            )],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Stale);
+    }
+
+    #[test]
+    fn status_reports_linked_copy_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        write(nvim_dir_path.join(".nvim"), "settings").expect("to write source file");
+        let home_dir_path = temp_dir.path().join("home");
+        let destination_dir = home_dir_path.join(".config/nvim");
+        create_dir_all(&destination_dir).expect("to create destination dir");
+        write(destination_dir.join(".nvim"), "settings").expect("to write copied file");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_with_strategy(
+                "nvim",
+                ".config/nvim",
+                LinkStrategy::Copy,
+            )],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Linked);
+    }
+
+    #[test]
+    fn status_reports_stale_copy_destination() {
+        let temp_dir = tempdir().expect("to create temp_dir");
+        let settings_dir_path = temp_dir.path().join("settings_dir");
+        let nvim_dir_path = settings_dir_path.join("nvim");
+        create_dir_all(&nvim_dir_path).expect("to create dir");
+        write(nvim_dir_path.join(".nvim"), "new settings").expect("to write source file");
+        let home_dir_path = temp_dir.path().join("home");
+        let destination_dir = home_dir_path.join(".config/nvim");
+        create_dir_all(&destination_dir).expect("to create destination dir");
+        write(destination_dir.join(".nvim"), "old settings").expect("to write stale copied file");
+
+        let manifest = Manifest {
+            manifest_items: vec![manifest_item_with_strategy(
+                "nvim",
+                ".config/nvim",
+                LinkStrategy::Copy,
+            )],
+        };
+
+        let report = status(&home_dir_path, &settings_dir_path, &manifest, &MockEnv::new())
+            .expect("to get status report");
+
+        assert_eq!(report[0].state, ItemState::Stale);
+    }
+}